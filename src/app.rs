@@ -0,0 +1,24 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+use tower_http::services::ServeDir;
+use tower_http::trace::TraceLayer;
+
+use crate::handlers::{contact_form_handler, contact_submit_handler, index_handler};
+use crate::state::AppState;
+
+/// Build the application router shared by the running server and tests.
+pub fn build_router(state: AppState) -> Router {
+    Router::new()
+        // Serve the main index.html at the root
+        .route("/", get(index_handler))
+        // Serve static files (CSS, JS, images, etc.)
+        .nest_service("/static", ServeDir::new("static"))
+        // Contact form endpoints using fragment-based approach
+        .route("/contact/form", get(contact_form_handler))
+        .route("/contact/form", post(contact_submit_handler))
+        // Gives every request a latency/status span, regardless of route.
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
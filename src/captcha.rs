@@ -0,0 +1,76 @@
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A freshly generated arithmetic challenge to show the user.
+pub struct Challenge {
+    pub token: String,
+    pub question: String,
+}
+
+struct Entry {
+    answer: i64,
+    expires_at: Instant,
+}
+
+/// Tracks outstanding captcha challenges so a submission can be checked
+/// against the answer it was actually shown, without trusting the client.
+/// Tokens are single-use: a successful or failed check both consume them.
+pub struct CaptchaStore {
+    entries: Mutex<HashMap<String, Entry>>,
+    ttl: Duration,
+}
+
+impl CaptchaStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    /// Generate a new arithmetic challenge and remember its answer.
+    pub fn generate(&self) -> Challenge {
+        let mut rng = rand::thread_rng();
+        let a = rng.gen_range(1..=9);
+        let b = rng.gen_range(1..=9);
+        let token: String = rng
+            .sample_iter(&Alphanumeric)
+            .take(16)
+            .map(char::from)
+            .collect();
+
+        self.entries.lock().unwrap().insert(
+            token.clone(),
+            Entry {
+                answer: a + b,
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+
+        Challenge {
+            token,
+            question: format!("{a} + {b}"),
+        }
+    }
+
+    /// Check a submitted answer against the token it was issued for. Single
+    /// use: the token is removed whether or not the answer matched.
+    pub fn verify(&self, token: &str, answer: i64) -> bool {
+        match self.entries.lock().unwrap().remove(token) {
+            Some(entry) => entry.expires_at > Instant::now() && entry.answer == answer,
+            None => false,
+        }
+    }
+
+    /// Drop challenges that were never answered before they expired.
+    pub fn purge_expired(&self) {
+        let now = Instant::now();
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|_, entry| entry.expires_at > now);
+    }
+}
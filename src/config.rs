@@ -0,0 +1,57 @@
+use config::{Config, File};
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::SettingsError;
+
+/// Supported contact form input types. New variants can be added here as the
+/// form grows beyond plain text and multi-line text.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldType {
+    Text,
+    Textarea { rows: u8 },
+}
+
+/// Describes one contact form field: what it's called, how it's labelled,
+/// whether it's required, and what kind of input renders it.
+#[derive(Debug, Deserialize, Clone)]
+pub struct FieldConfig {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub required: bool,
+    pub field_type: FieldType,
+}
+
+/// The set of fields the contact form renders and validates, loaded from
+/// `config/contact_form.toml` so an operator can add or remove a field
+/// without touching Rust or the template.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ContactFormConfig {
+    pub fields: Vec<FieldConfig>,
+}
+
+impl ContactFormConfig {
+    /// Load the contact form field configuration from its default location
+    /// alongside `config/default.toml`.
+    pub fn load_default() -> Result<Self, SettingsError> {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let path = Path::new(manifest_dir)
+            .join("config")
+            .join("contact_form.toml");
+
+        Self::load(&path)
+    }
+
+    fn load(path: &Path) -> Result<Self, SettingsError> {
+        let config = Config::builder()
+            .add_source(File::from(path))
+            .build()
+            .map_err(SettingsError::ConfigBuild)?;
+
+        config
+            .try_deserialize()
+            .map_err(SettingsError::ConfigBuild)
+    }
+}
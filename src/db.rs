@@ -0,0 +1,47 @@
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::AppError;
+
+/// Connect to the submissions database and bring its schema up to date.
+pub async fn connect(database_url: &str) -> Result<SqlitePool, AppError> {
+    // An in-memory database is private to the connection that opened it
+    // unless the URL opts into a shared cache, so a pool of more than one
+    // connection would have each request randomly landing on its own empty
+    // database. Cap it at one connection instead of relying on pool
+    // scheduling to keep every query on the same connection.
+    let max_connections = if database_url.contains(":memory:") { 1 } else { 5 };
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(max_connections)
+        .connect(database_url)
+        .await
+        .map_err(|e| AppError::InternalError(format!("Failed to connect to database: {e}")))?;
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .map_err(|e| AppError::InternalError(format!("Failed to run database migrations: {e}")))?;
+
+    Ok(pool)
+}
+
+/// Persist a successful contact submission. Field values are stored as a
+/// single JSON object so the set of configured fields can grow without a
+/// schema migration per field.
+pub async fn store_submission(
+    pool: &SqlitePool,
+    values: &HashMap<String, String>,
+) -> Result<(), AppError> {
+    let values_json = serde_json::to_string(values)
+        .map_err(|e| AppError::InternalError(format!("Failed to serialize submission: {e}")))?;
+
+    sqlx::query("INSERT INTO submissions (values_json) VALUES (?)")
+        .bind(values_json)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::InternalError(format!("Failed to store submission: {e}")))?;
+
+    Ok(())
+}
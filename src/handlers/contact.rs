@@ -1,72 +1,127 @@
 use askama::Template;
 use async_stream::stream;
 use axum::{
-    extract::Form,
-    response::{IntoResponse, Response, Sse},
+    extract::{Form, State},
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response, Sse},
 };
 use datastar::prelude::{PatchElements, PatchSignals};
-use serde::Deserialize;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
 use serde_json::json;
+use std::collections::HashMap;
 use std::convert::Infallible;
-use tracing::info;
+use tracing::{error, info};
 
-#[derive(Template)]
-#[template(path = "contact_form.html")]
-pub struct ContactFormTemplate;
+use crate::config::{ContactFormConfig, FieldConfig, FieldType};
+use crate::AppState;
+
+/// Datastar sends this header on every request made through its runtime, so
+/// we can tell a browser with JavaScript disabled (or no Datastar) apart from
+/// one driving the form via signals.
+const DATASTAR_REQUEST_HEADER: &str = "datastar-request";
 
-/// Contact form data structure - simplified for standard form submission
-#[derive(Deserialize)]
-pub struct ContactFormData {
-    pub name: String,
-    pub email: String,
-    pub phone: Option<String>,
-    pub service: Option<String>,
-    pub message: Option<String>,
+/// The fields the form was submitted with, keyed by their configured `id`
+/// (plus the `captcha`/`captcha_token` pair, which aren't part of the
+/// configured contact fields).
+pub type ContactFormValues = HashMap<String, String>;
+
+#[derive(Template, Default)]
+#[template(path = "contact_form.html")]
+pub struct ContactFormTemplate {
+    pub fields: Vec<FieldConfig>,
+    pub values: ContactFormValues,
+    pub errors: HashMap<String, String>,
+    pub success: bool,
+    pub captcha_token: String,
+    pub captcha_question: String,
 }
 
-/// Handler to serve the contact form fragment
-/// Called by @get('/contact/form')
-pub async fn contact_form_handler() -> Result<Response, crate::AppError> {
-    let html = ContactFormTemplate.render()?;
-    Ok(create_sse_response(html).into_response())
+impl ContactFormTemplate {
+    /// The Datastar signals the form is seeded with, JSON-encoded so a field
+    /// value containing a double quote (e.g. a message `say "hi"`) can't break out of the
+    /// `data-signals` attribute's nested string literal. HTML-escaping the
+    /// attribute alone isn't enough: the browser decodes entities back to
+    /// literal characters before Datastar ever reads the value, so the JS
+    /// string inside it has to be escaped too, which is exactly what
+    /// `serde_json` already does for us.
+    fn initial_signals(&self) -> String {
+        let mut signals = json!({
+            "showSuccess": self.success,
+            "showError": !self.errors.is_empty(),
+            "errorMessage": "",
+        });
+        for field in &self.fields {
+            signals[field.id.as_str()] = json!(self.values.get(&field.id).cloned().unwrap_or_default());
+        }
+        signals.to_string()
+    }
 }
 
-/// Validate contact form and return Result with custom validation errors
-pub fn validate_contact_form(form: &ContactFormData) -> Result<(), ContactFormError> {
-    let name = form.name.trim();
+/// A single field's validation failure, keyed to the input it belongs to so
+/// the template can put the message next to the right field.
+#[derive(Debug)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
 
-    if name.is_empty() {
-        return Err(ContactFormError::new("Name is required"));
+impl FieldError {
+    fn new(field: &str, message: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
     }
+}
 
-    if name.len() < 2 {
-        return Err(ContactFormError::new("Name must be at least 2 characters"));
-    }
+/// Validate the submitted values against the configured fields, returning
+/// every failure at once so the re-rendered form can show all of them rather
+/// than one at a time.
+pub fn validate(contact_form: &ContactFormConfig, values: &ContactFormValues) -> Result<(), Vec<FieldError>> {
+    let mut errors = Vec::new();
 
-    let email = form.email.trim();
+    for field in &contact_form.fields {
+        let value = values.get(&field.id).map(|v| v.trim()).unwrap_or("");
 
-    if email.is_empty() {
-        return Err(ContactFormError::new("Email is required"));
-    }
+        if field.required && value.is_empty() {
+            errors.push(FieldError::new(&field.id, &format!("{} is required", field.label)));
+            continue;
+        }
 
-    if !is_valid_email(&form.email) {
-        return Err(ContactFormError::new("Please enter a valid email address"));
-    }
+        if field.id == "email" && !value.is_empty() && !is_valid_email(value) {
+            errors.push(FieldError::new(&field.id, "Please enter a valid email address"));
+        }
 
-    Ok(())
-}
+        if field.id == "name" && !value.is_empty() && value.len() < 2 {
+            errors.push(FieldError::new(&field.id, "Name must be at least 2 characters"));
+        }
+    }
 
-/// Custom error type for contact form validation
-#[derive(Debug)]
-pub struct ContactFormError {
-    error_message: String,
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
 }
 
-impl ContactFormError {
-    pub fn new(message: &str) -> Self {
-        Self {
-            error_message: message.to_string(),
-        }
+/// Check the submitted captcha answer against the token it was issued for.
+/// The token is single-use, so this also invalidates it regardless of the
+/// outcome.
+fn verify_captcha(state: &AppState, values: &ContactFormValues) -> Result<(), FieldError> {
+    let token = values.get("captcha_token").map(String::as_str).unwrap_or("");
+    let answer = values
+        .get("captcha")
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .unwrap_or(i64::MIN);
+
+    if state.captcha.verify(token, answer) {
+        Ok(())
+    } else {
+        Err(FieldError::new(
+            "captcha",
+            "That answer isn't quite right, please try again",
+        ))
     }
 }
 
@@ -99,60 +154,178 @@ pub fn is_valid_email(email: &str) -> bool {
     domain.contains('.')
 }
 
+/// Handler to serve the contact form fragment
+/// Called by @get('/contact/form')
+pub async fn contact_form_handler(State(state): State<AppState>) -> Result<Response, crate::AppError> {
+    let challenge = state.captcha.generate();
+    let template = ContactFormTemplate {
+        fields: state.contact_form.fields.clone(),
+        captcha_token: challenge.token,
+        captcha_question: challenge.question,
+        ..Default::default()
+    };
+    let html = template.render()?;
+    Ok(create_sse_response(html).into_response())
+}
+
 /// Handler for form submission
 /// Receives form data (not JSON signals)
-pub async fn contact_submit_handler(Form(form): Form<ContactFormData>) -> Response {
-    log_contact_form_submission(&form);
-
-    // Validate form and return early if invalid
-    if let Err(validation_error) = validate_contact_form(&form) {
-        return create_error_response(&form, validation_error);
+///
+/// Requests made by the Datastar runtime (identified by the
+/// `Datastar-Request` header) get their result streamed back as a
+/// `datastar-patch-elements` SSE event with the re-rendered form followed by
+/// a `datastar-patch-signals` event, so the page updates without a
+/// navigation. Any other client gets the re-rendered form as plain HTML
+/// (HTTP 422 on error), preserving what was typed.
+#[tracing::instrument(skip_all, fields(request_id = %generate_request_id()))]
+pub async fn contact_submit_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Form(values): Form<ContactFormValues>,
+) -> Result<Response, crate::AppError> {
+    log_contact_form_submission(&values);
+
+    let datastar = wants_datastar(&headers);
+    let sanitized_values = sanitize_values(&values);
+
+    let mut field_errors = validate(&state.contact_form, &sanitized_values).err().unwrap_or_default();
+    if let Err(captcha_error) = verify_captcha(&state, &values) {
+        field_errors.push(captcha_error);
     }
 
-    // Process valid form data
-    log_successful_submission(&form);
-
-    // TODO: Send email, save to database, etc.
-    // For now, we'll just log it
+    if field_errors.is_empty() {
+        match record_submission(&state, &sanitized_values).await {
+            Ok(()) => {
+                info!(
+                    name = sanitized_values.get("name").map(String::as_str).unwrap_or(""),
+                    email = sanitized_values.get("email").map(String::as_str).unwrap_or(""),
+                    "Successful contact form submission"
+                );
+
+                // The captcha token was already consumed by `verify_captcha`,
+                // so the form still sitting in the DOM (behind
+                // `data-show="!$showSuccess"`) needs a fresh challenge before
+                // the user can send another message.
+                let challenge = state.captcha.generate();
+                let template = ContactFormTemplate {
+                    fields: state.contact_form.fields.clone(),
+                    success: true,
+                    captcha_token: challenge.token,
+                    captcha_question: challenge.question,
+                    ..Default::default()
+                };
+                let html = template.render()?;
+
+                return Ok(if datastar {
+                    let mut signals = json!({
+                        "showSuccess": true,
+                        "showError": false,
+                        "errorMessage": "",
+                    });
+                    for field in &state.contact_form.fields {
+                        signals[field.id.as_str()] = json!("");
+                    }
+                    create_form_update_response(html, signals)
+                } else {
+                    Html(html).into_response()
+                });
+            }
+            Err(err) => {
+                // A DB or SMTP failure shouldn't surface as a 500: render it
+                // through the same error path as a validation failure so the
+                // user's input is preserved and they get something to act on.
+                error!("Failed to record contact form submission: {err}");
+                field_errors.push(FieldError::new(
+                    "form",
+                    "Something went wrong on our end, please try again.",
+                ));
+            }
+        }
+    }
 
-    create_success_response()
+    // The captcha token was already consumed by `verify_captcha`, so the
+    // re-rendered form needs a fresh challenge either way.
+    let challenge = state.captcha.generate();
+    let message = field_errors
+        .iter()
+        .map(|e| e.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+    error!("Rejected contact form submission: {message}");
+    let errors = field_errors
+        .into_iter()
+        .map(|e| (e.field, e.message))
+        .collect();
+    let template = ContactFormTemplate {
+        fields: state.contact_form.fields.clone(),
+        values: sanitized_values,
+        errors,
+        success: false,
+        captcha_token: challenge.token,
+        captcha_question: challenge.question,
+    };
+    let html = template.render()?;
+
+    if datastar {
+        // Patch the form fragment (new field values, inline errors, the
+        // new captcha question/token) and the Datastar signals (which
+        // Datastar only merges in, rather than overwrites, from markup).
+        Ok(create_form_update_response(
+            html,
+            json!({
+                "showSuccess": false,
+                "showError": true,
+                "errorMessage": message,
+            }),
+        ))
+    } else {
+        Ok((StatusCode::UNPROCESSABLE_ENTITY, Html(html)).into_response())
+    }
 }
 
-fn create_success_response() -> Response {
-    let signals = json!({
-        "showSuccess": true,
-        "showError": false,
-        "errorMessage": "",
-        // Reset form fields
-        "name": "",
-        "email": "",
-        "phone": "",
-        "service": "",
-        "message": ""
-    });
+/// Persist a submission and email it out. Both steps can fail independently
+/// (DB down, SMTP relay unreachable); the caller treats either failure as a
+/// form-level error rather than a 500.
+async fn record_submission(state: &AppState, values: &ContactFormValues) -> Result<(), crate::AppError> {
+    // `values` also carries the `captcha`/`captcha_token` pair, which has
+    // already done its job by this point and has no business in the
+    // submissions table, so only persist the configured fields.
+    let persisted_values = configured_values(&state.contact_form, values);
+
+    // Persist first so the submission survives even if sending the
+    // notification email fails.
+    crate::db::store_submission(&state.db, &persisted_values).await?;
+    state.mailer.send(&state.contact_form, values).await?;
+    Ok(())
+}
 
-    Sse::new(stream! {
-        let patch = PatchSignals::new(signals.to_string());
-        yield Ok::<_, Infallible>(patch.write_as_axum_sse_event());
-    })
-    .into_response()
+/// Restrict a submitted values map down to the configured contact form
+/// fields, dropping anything else (e.g. the spent captcha token/answer).
+fn configured_values(contact_form: &ContactFormConfig, values: &ContactFormValues) -> ContactFormValues {
+    contact_form
+        .fields
+        .iter()
+        .filter_map(|field| values.get(&field.id).map(|value| (field.id.clone(), value.clone())))
+        .collect()
 }
 
-fn create_error_response(form: &ContactFormData, validation_error: ContactFormError) -> Response {
-    let escaped_error = validation_error.error_message.replace('"', "\\\"");
-    let signals = json!({
-        "showSuccess": false,
-        "showError": true,
-        "errorMessage": escaped_error,
-        // Preserve form fields
-        "name": sanitize_input(&form.name),
-        "email": sanitize_input(&form.email),
-        "phone": form.phone.as_ref().map(|s| sanitize_input(s)).unwrap_or_default(),
-        "service": form.service.as_ref().map(|s| sanitize_input(s)).unwrap_or_default(),
-        "message": form.message.as_ref().map(|s| sanitize_input(s)).unwrap_or_default()
-    });
+/// Whether this request came from the Datastar runtime and should get an SSE
+/// signals patch back instead of a full re-rendered form.
+fn wants_datastar(headers: &HeaderMap) -> bool {
+    headers
+        .get(DATASTAR_REQUEST_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
 
+/// Patch both the form fragment and the Datastar signals in one SSE
+/// response, used when the re-rendered markup itself changed (new captcha
+/// challenge, preserved input, inline errors).
+fn create_form_update_response(html: String, signals: serde_json::Value) -> Response {
     Sse::new(stream! {
+        let elements = PatchElements::new(html);
+        yield Ok::<_, Infallible>(elements.write_as_axum_sse_event());
         let patch = PatchSignals::new(signals.to_string());
         yield Ok::<_, Infallible>(patch.write_as_axum_sse_event());
     })
@@ -160,43 +333,18 @@ fn create_error_response(form: &ContactFormData, validation_error: ContactFormEr
 }
 
 /// Log contact form submission details
-fn log_contact_form_submission(form: &ContactFormData) {
-    let sanitized_fields = FormLogFields {
-        name: sanitize_input(&form.name),
-        email: sanitize_input(&form.email),
-        phone: form.phone.as_ref().map(|s| sanitize_input(s)),
-        service: form.service.as_ref().map(|s| sanitize_input(s)),
-        message: form.message.as_ref().map(|s| sanitize_input(s)),
-    };
-
-    info!(
-        "Received contact form submission: Name: {}, Email: {}, Phone: {:?}, Service: {:?}, Message: {:?}",
-        sanitized_fields.name,
-        sanitized_fields.email,
-        sanitized_fields.phone,
-        sanitized_fields.service,
-        sanitized_fields.message
-    );
+fn log_contact_form_submission(values: &ContactFormValues) {
+    info!("Received contact form submission: {:?}", values);
 }
 
-struct FormLogFields {
-    name: String,
-    email: String,
-    phone: Option<String>,
-    service: Option<String>,
-    message: Option<String>,
-}
-
-/// Log successful form validation
-fn log_successful_submission(form: &ContactFormData) {
-    let sanitized_name = sanitize_input(&form.name);
-    let sanitized_email = sanitize_input(&form.email);
-
-    info!(
-        "Successfully validated contact form from: {} ({})",
-        sanitized_name,
-        sanitized_email
-    );
+/// Generate a short, opaque id to correlate the log lines of a single
+/// request, independent of any id the reverse proxy or client might set.
+fn generate_request_id() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(12)
+        .map(char::from)
+        .collect()
 }
 
 /// Create SSE response with content
@@ -209,10 +357,15 @@ pub fn create_sse_response(html: String) -> impl IntoResponse {
 
 /// Basic input sanitization to prevent XSS attacks
 pub fn sanitize_input(input: &str) -> String {
-    let sanitized_chars = input
-        .chars()
-        .filter(|c| c.is_ascii() && !c.is_control());
+    let sanitized_chars = input.chars().filter(|c| c.is_ascii() && !c.is_control());
 
     let sanitized: String = sanitized_chars.collect();
     sanitized.trim().to_string()
-}
\ No newline at end of file
+}
+
+fn sanitize_values(values: &ContactFormValues) -> ContactFormValues {
+    values
+        .iter()
+        .map(|(key, value)| (key.clone(), sanitize_input(value)))
+        .collect()
+}
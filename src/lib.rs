@@ -1,5 +1,17 @@
+pub mod app;
+pub mod captcha;
+pub mod config;
+pub mod db;
 pub mod error;
+pub mod handlers;
+pub mod mailer;
 pub mod settings;
+pub mod state;
 
+pub use app::build_router;
+pub use captcha::CaptchaStore;
+pub use config::ContactFormConfig;
 pub use error::AppError;
-pub use settings::{Settings, SettingsError};
\ No newline at end of file
+pub use mailer::Mailer;
+pub use settings::{Settings, SettingsError};
+pub use state::AppState;
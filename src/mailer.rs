@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use crate::config::ContactFormConfig;
+use crate::settings::MailSettings;
+use crate::AppError;
+use lettre::{
+    message::Mailbox, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+
+/// Sends contact form submissions over SMTP. Constructed once at startup
+/// from [`MailSettings`] and shared across requests via Axum state.
+pub struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Mailbox,
+}
+
+impl Mailer {
+    pub fn new(settings: &MailSettings) -> Result<Self, AppError> {
+        let from: Mailbox = settings
+            .from
+            .parse()
+            .map_err(|e| AppError::InternalError(format!("Invalid mail `from` address: {e}")))?;
+        let to: Mailbox = settings
+            .to
+            .parse()
+            .map_err(|e| AppError::InternalError(format!("Invalid mail `to` address: {e}")))?;
+
+        let credentials = Credentials::new(
+            settings.smtp.username.clone(),
+            settings.smtp.password.clone(),
+        );
+
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.smtp.domain)
+            .map_err(|e| AppError::InternalError(format!("Failed to configure SMTP transport: {e}")))?
+            .credentials(credentials)
+            .build();
+
+        Ok(Self { transport, from, to })
+    }
+
+    /// Send a contact submission, formatting the body from the configured
+    /// fields in the order an operator declared them.
+    pub async fn send(
+        &self,
+        contact_form: &ContactFormConfig,
+        values: &HashMap<String, String>,
+    ) -> Result<(), AppError> {
+        let subject_name = values
+            .get("name")
+            .map(|name| name.trim())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("a visitor");
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(self.to.clone())
+            .subject(format!("New contact form submission from {subject_name}"))
+            .body(format_contact_email(contact_form, values))
+            .map_err(|e| AppError::InternalError(format!("Failed to build email: {e}")))?;
+
+        self.transport
+            .send(&email)
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to send contact email: {e}")))?;
+
+        Ok(())
+    }
+}
+
+fn format_contact_email(contact_form: &ContactFormConfig, values: &HashMap<String, String>) -> String {
+    contact_form
+        .fields
+        .iter()
+        .map(|field| {
+            let value = values.get(&field.id).map(String::as_str).unwrap_or("-");
+            format!("{}: {}", field.label, value)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
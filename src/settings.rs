@@ -23,6 +23,25 @@ pub struct LogSettings {
     pub format: String,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct SmtpSettings {
+    pub domain: String,
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MailSettings {
+    pub from: String,
+    pub to: String,
+    pub smtp: SmtpSettings,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DatabaseSettings {
+    pub url: String,
+}
+
 #[derive(Error, Debug)]
 pub enum SettingsError {
     #[error("Failed to determine the current directory")]
@@ -38,6 +57,8 @@ pub struct Settings {
     pub server: ServerSettings,
     pub log: LogSettings,
     pub metadata: Metadata,
+    pub mail: MailSettings,
+    pub database: DatabaseSettings,
 }
 
 // Global settings instance
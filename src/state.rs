@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use sqlx::SqlitePool;
+
+use crate::captcha::CaptchaStore;
+use crate::config::ContactFormConfig;
+use crate::mailer::Mailer;
+
+/// Shared application state handed to handlers via Axum's `State` extractor.
+#[derive(Clone)]
+pub struct AppState {
+    pub mailer: Arc<Mailer>,
+    pub contact_form: Arc<ContactFormConfig>,
+    pub db: SqlitePool,
+    pub captcha: Arc<CaptchaStore>,
+}
@@ -1,97 +1,121 @@
-use std::process::Command;
-use std::thread;
-use std::time::Duration;
+use askama::Template;
+use greenfields_of_cambridge::config::{FieldConfig, FieldType};
+use greenfields_of_cambridge::handlers::contact::ContactFormTemplate;
+
+/// The fields configured in `config/contact_form.toml`, mirrored here so the
+/// template can be rendered and inspected without loading the config file.
+fn default_fields() -> Vec<FieldConfig> {
+    vec![
+        FieldConfig {
+            id: "name".to_string(),
+            label: "Name".to_string(),
+            required: true,
+            field_type: FieldType::Text,
+        },
+        FieldConfig {
+            id: "email".to_string(),
+            label: "Email".to_string(),
+            required: true,
+            field_type: FieldType::Text,
+        },
+        FieldConfig {
+            id: "phone".to_string(),
+            label: "Phone".to_string(),
+            required: false,
+            field_type: FieldType::Text,
+        },
+        FieldConfig {
+            id: "service".to_string(),
+            label: "Service".to_string(),
+            required: false,
+            field_type: FieldType::Text,
+        },
+        FieldConfig {
+            id: "message".to_string(),
+            label: "Message".to_string(),
+            required: true,
+            field_type: FieldType::Textarea { rows: 5 },
+        },
+    ]
+}
 
 #[test]
 fn test_contact_form_datastar_signals() {
-    // Check that the contact form template has proper Datastar signals
-    let template_content = include_str!("../templates/contact_form.html");
+    // Render the contact form with the default, config-driven fields and
+    // check that Datastar signals are wired up correctly in the output.
+    let template = ContactFormTemplate {
+        fields: default_fields(),
+        ..Default::default()
+    };
+    let rendered = template.render().expect("contact form should render");
 
     // Test 1: Verify data-signals is defined with proper structure
     assert!(
-        template_content.contains("data-signals="),
+        rendered.contains("data-signals="),
         "Contact form must have data-signals defined"
     );
 
     assert!(
-        template_content.contains("showSuccess") && template_content.contains("showError"),
+        rendered.contains("showSuccess") && rendered.contains("showError"),
         "Data signals must include showSuccess and showError"
     );
 
     assert!(
-        template_content.contains("errorMessage") && template_content.contains("name") && template_content.contains("email"),
+        rendered.contains("errorMessage") && rendered.contains("name") && rendered.contains("email"),
         "Data signals must include errorMessage, name, and email"
     );
 
     // Test 2: Verify data-show attributes for visibility control
     assert!(
-        template_content.contains("data-show=\"$showSuccess\""),
+        rendered.contains("data-show=\"$showSuccess\""),
         "Success section should be controlled by showSuccess signal"
     );
 
     assert!(
-        template_content.contains("data-show=\"$showError\""),
+        rendered.contains("data-show=\"$showError\""),
         "Error section should be controlled by showError signal"
     );
 
     assert!(
-        template_content.contains("data-show=\"!$showSuccess\""),
+        rendered.contains("data-show=\"!$showSuccess\""),
         "Form should be hidden when success is shown"
     );
 
     // Test 3: Verify data-text for error message display
     assert!(
-        template_content.contains("data-text=\"$errorMessage\""),
+        rendered.contains("data-text=\"$errorMessage\""),
         "Error message should use data-text with errorMessage signal"
     );
 
-    // Test 4: Verify data-bind attributes on form inputs
+    // Test 4: Verify data-bind attributes on form inputs, generated from config
     assert!(
-        template_content.contains("data-bind=\"name\""),
+        rendered.contains("data-bind=\"name\""),
         "Name input should have data-bind attribute"
     );
 
     assert!(
-        template_content.contains("data-bind=\"email\""),
+        rendered.contains("data-bind=\"email\""),
         "Email input should have data-bind attribute"
     );
 
     assert!(
-        template_content.contains("data-bind=\"phone\""),
+        rendered.contains("data-bind=\"phone\""),
         "Phone input should have data-bind attribute"
     );
 
     assert!(
-        template_content.contains("data-bind=\"service\""),
-        "Service select should have data-bind attribute"
+        rendered.contains("data-bind=\"service\""),
+        "Service input should have data-bind attribute"
     );
 
     assert!(
-        template_content.contains("data-bind=\"message\""),
+        rendered.contains("data-bind=\"message\""),
         "Message textarea should have data-bind attribute"
     );
 
     // Test 5: Verify reset functionality on success
     assert!(
-        template_content.contains("$showSuccess = false; $showError = false"),
+        rendered.contains("$showSuccess = false; $showError = false"),
         "Send Another Message button should reset visibility signals"
     );
 }
-
-#[test]
-fn test_server_starts_successfully() {
-    // Test that the server can start without panicking
-    let mut child = Command::new("cargo")
-        .arg("run")
-        .spawn()
-        .expect("Failed to start server");
-
-    // Give the server time to start
-    thread::sleep(Duration::from_secs(2));
-
-    // Kill the server process
-    child.kill().expect("Failed to kill server process");
-
-    // If we get here, the server started without panicking on startup
-    assert!(true, "Server should start successfully");
-}
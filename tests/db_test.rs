@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use greenfields_of_cambridge::db;
+
+/// Each test gets its own in-memory SQLite database so submissions from one
+/// test can never leak into another.
+async fn fresh_pool() -> sqlx::SqlitePool {
+    db::connect("sqlite::memory:")
+        .await
+        .expect("in-memory database should connect and migrate")
+}
+
+#[tokio::test]
+async fn stores_a_submission() {
+    let pool = fresh_pool().await;
+
+    let mut values = HashMap::new();
+    values.insert("name".to_string(), "Ada Lovelace".to_string());
+    values.insert("email".to_string(), "ada@example.com".to_string());
+
+    db::store_submission(&pool, &values)
+        .await
+        .expect("submission should be stored");
+
+    let row: (String,) = sqlx::query_as("SELECT values_json FROM submissions")
+        .fetch_one(&pool)
+        .await
+        .expect("the stored submission should be readable back");
+
+    let stored: HashMap<String, String> =
+        serde_json::from_str(&row.0).expect("stored values should round-trip as JSON");
+    assert_eq!(stored.get("name"), Some(&"Ada Lovelace".to_string()));
+    assert_eq!(stored.get("email"), Some(&"ada@example.com".to_string()));
+}
+
+#[tokio::test]
+async fn assigns_an_incrementing_id_per_submission() {
+    let pool = fresh_pool().await;
+
+    db::store_submission(&pool, &HashMap::new()).await.unwrap();
+    db::store_submission(&pool, &HashMap::new()).await.unwrap();
+
+    let ids: Vec<(i64,)> = sqlx::query_as("SELECT id FROM submissions ORDER BY id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![(1,), (2,)]);
+}
@@ -0,0 +1,87 @@
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::time::Duration;
+
+use greenfields_of_cambridge::{build_router, AppState, CaptchaStore, ContactFormConfig, Mailer, Settings};
+
+/// Bind the app to an OS-assigned port, serve it on a background task, and
+/// return the base URL. Using an ephemeral port (rather than shelling out to
+/// `cargo run` and sleeping) makes startup assertions deterministic and fast.
+async fn spawn_app() -> String {
+    // `Settings::initialize` can only succeed once per process; later tests
+    // in this binary hit the already-initialized case, which is fine as
+    // long as settings were loaded at all.
+    let _ = Settings::initialize();
+    let settings = Settings::get();
+
+    let state = AppState {
+        mailer: Arc::new(Mailer::new(&settings.mail).expect("failed to build test mailer")),
+        contact_form: Arc::new(ContactFormConfig::load_default().expect("failed to load contact form config")),
+        db: greenfields_of_cambridge::db::connect("sqlite::memory:")
+            .await
+            .expect("failed to connect to in-memory test database"),
+        captcha: Arc::new(CaptchaStore::new(Duration::from_secs(5 * 60))),
+    };
+    let app = build_router(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind to a random port");
+    let port = listener.local_addr().expect("listener has no local address").port();
+    listener.set_nonblocking(true).expect("failed to set listener non-blocking");
+    let listener = tokio::net::TcpListener::from_std(listener).expect("failed to hand listener to tokio");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("test server failed");
+    });
+
+    format!("http://127.0.0.1:{port}")
+}
+
+#[tokio::test]
+async fn index_page_is_served_at_root() {
+    let address = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client.get(&address).send().await.expect("request to / failed");
+
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn contact_form_fragment_is_served() {
+    let address = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{address}/contact/form"))
+        .send()
+        .await
+        .expect("request to /contact/form failed");
+
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn contact_form_submission_without_a_captcha_answer_is_rejected() {
+    let address = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{address}/contact/form"))
+        .form(&[
+            ("name", "Ada Lovelace"),
+            ("email", "ada@example.com"),
+            ("message", "Hello there"),
+            ("captcha_token", ""),
+            ("captcha", ""),
+        ])
+        .send()
+        .await
+        .expect("request to /contact/form failed");
+
+    assert_eq!(response.status(), reqwest::StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = response.text().await.expect("failed to read response body");
+    // The message is HTML-escaped in the rendered form, so match the part of
+    // it after the apostrophe.
+    assert!(body.contains("quite right, please try again"));
+}